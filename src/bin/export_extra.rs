@@ -0,0 +1,9 @@
+//! Runs after `perseus export`, mirroring Perseus' own exporter binary, to
+//! drop `feed.xml` and `sitemap.xml` in alongside the generated HTML.
+
+use std::path::Path;
+
+fn main() {
+    site::feed::write_export_artifacts(Path::new("dist"))
+        .expect("failed to write feed.xml/sitemap.xml to dist");
+}
@@ -1,12 +1,29 @@
 use chrono::FixedOffset;
 use markdown::{to_html_with_options, CompileOptions, Constructs, Options, ParseOptions};
+use once_cell::sync::OnceCell;
 use perseus::ReactiveState;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{DirEntry, File};
 use std::io;
 use std::io::Read;
 use std::path::Path;
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+/// Theme used for build-time syntax highlighting; anything in syntect's
+/// bundled defaults works here. Carried onto `Post::highlight_theme` so
+/// other code (e.g. matching page CSS to the highlighter's background) can
+/// see which theme is in use without hardcoding it a second time.
+pub const HIGHLIGHT_THEME: &str = "base16-ocean.dark";
+
+/// Locale a post is authored in when no `index.<locale>.md` matches the
+/// requested one; mirrors `PerseusApp::locales`' default locale.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales the site is published in, passed to `PerseusApp::locales`.
+pub const LOCALES: &[&str] = &["en", "fr"];
 
 pub fn get_blog_directories() -> Vec<String> {
     let listing: io::Result<Vec<DirEntry>> = Path::new("content/blog")
@@ -35,6 +52,9 @@ pub struct Post {
     pub html: String,
     pub path: String,
     pub image: Option<String>,
+    pub tags: Vec<String>,
+    pub locale: String,
+    pub highlight_theme: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -43,26 +63,84 @@ pub struct FrontMatter {
     pub date: chrono::DateTime<FixedOffset>,
     pub description: Option<String>,
     pub image: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum PostError {
+    /// There's neither a `content/blog/<path>/index.<locale>.md` for the
+    /// requested locale nor one for `DEFAULT_LOCALE` on disk for this path.
+    NotFound(String),
+    Read(io::Error),
+    MissingFrontMatter,
+    FrontMatter(serde_yaml::Error),
+    Render(markdown::message::Message),
+}
+
+impl std::fmt::Display for PostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PostError::NotFound(path) => {
+                write!(
+                    f,
+                    "no post found at content/blog/{path}/ (no index.<locale>.md or index.{DEFAULT_LOCALE}.md)"
+                )
+            }
+            PostError::Read(e) => write!(f, "cannot read blog md: {e}"),
+            PostError::MissingFrontMatter => write!(f, "front matter missing"),
+            PostError::FrontMatter(e) => write!(f, "cannot parse front matter: {e}"),
+            PostError::Render(e) => write!(f, "cannot render post html: {e}"),
+        }
+    }
 }
 
-pub fn get_front_matter(contents: &str) -> FrontMatter {
+impl std::error::Error for PostError {}
+
+pub fn get_front_matter(contents: &str) -> Result<FrontMatter, PostError> {
     if let Some(("", rest)) = contents.split_once("---") {
         // Parse front matter.
         if let Some((front_matter_str, _body_str)) = rest.split_once("---") {
-            return serde_yaml::from_str(front_matter_str).expect("cannot parse front matter");
+            return serde_yaml::from_str(front_matter_str).map_err(PostError::FrontMatter);
         }
     }
-    panic!("front matter missing");
+    Err(PostError::MissingFrontMatter)
 }
 
-pub fn get_post_for_path(path: &String) -> Post {
-    let mut file = File::open(Path::new("content/blog").join(path).join("index.md"))
-        .expect("cannot open blog md");
+fn locale_file_path(path: &str, locale: &str) -> std::path::PathBuf {
+    Path::new("content/blog")
+        .join(path)
+        .join(format!("index.{locale}.md"))
+}
+
+/// Every locale that has its own `index.<locale>.md` for this post, for
+/// building `hreflang` alternate links.
+pub fn get_available_locales(path: &str) -> Vec<String> {
+    LOCALES
+        .iter()
+        .filter(|locale| locale_file_path(path, locale).exists())
+        .map(|locale| locale.to_string())
+        .collect()
+}
+
+pub fn get_post_for_path(path: &str, locale: &str) -> Result<Post, PostError> {
+    let requested_file = locale_file_path(path, locale);
+    let (file_path, resolved_locale) = if requested_file.exists() {
+        (requested_file, locale)
+    } else {
+        let default_file = locale_file_path(path, DEFAULT_LOCALE);
+        if !default_file.exists() {
+            return Err(PostError::NotFound(path.to_string()));
+        }
+        (default_file, DEFAULT_LOCALE)
+    };
+
+    let mut file = File::open(file_path).map_err(PostError::Read)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)
-        .expect("cannot read blog md");
+        .map_err(PostError::Read)?;
 
-    let front_matter = get_front_matter(&contents);
+    let front_matter = get_front_matter(&contents)?;
 
     let html = to_html_with_options(
         &contents,
@@ -80,7 +158,8 @@ pub fn get_post_for_path(path: &String) -> Post {
             },
         },
     )
-    .expect("cannot render post html");
+    .map_err(PostError::Render)?;
+    let html = highlight_code_blocks(&html);
 
     let image = front_matter.image.or_else(|| {
         let re = Regex::new(r#"src="([^"]+)""#).unwrap();
@@ -88,12 +167,110 @@ pub fn get_post_for_path(path: &String) -> Post {
     });
 
     // whatever convert it twice. Should probably just manually pull the yaml instead.
-    Post {
-        path: path.clone(),
+    Ok(Post {
+        path: path.to_string(),
         title: front_matter.title,
         date: front_matter.date,
         description: front_matter.description,
         html,
         image,
-    }
+        tags: front_matter.tags,
+        locale: resolved_locale.to_string(),
+        highlight_theme: HIGHLIGHT_THEME.to_string(),
+    })
+}
+
+static SYNTAX_SET: OnceCell<SyntaxSet> = OnceCell::new();
+static THEME_SET: OnceCell<syntect::highlighting::ThemeSet> = OnceCell::new();
+
+/// Post-processes fenced code blocks (`<pre><code class="language-xxx">`)
+/// emitted by the Markdown compiler into pre-colored `<span style=...>`
+/// markup, so pages need zero client JS for highlighting. Unknown languages
+/// are left untouched.
+fn highlight_code_blocks(html: &str) -> String {
+    let syntax_set = SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines);
+    let theme_set = THEME_SET.get_or_init(syntect::highlighting::ThemeSet::load_defaults);
+    let theme = &theme_set.themes[HIGHLIGHT_THEME];
+
+    let re = Regex::new(r#"(?s)<pre><code class="language-([\w-]+)">(.*?)</code></pre>"#).unwrap();
+    re.replace_all(html, |caps: &regex::Captures| {
+        let block = &caps[0];
+        let code = unescape_html(&caps[2]);
+        syntax_set
+            .find_syntax_by_token(&caps[1])
+            .and_then(|syntax| highlighted_html_for_string(&code, syntax_set, syntax, theme).ok())
+            .unwrap_or_else(|| block.to_string())
+    })
+    .into_owned()
+}
+
+fn unescape_html(raw: &str) -> String {
+    raw.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+static ALL_POSTS: OnceCell<HashMap<String, (Vec<Post>, HashMap<String, Post>)>> = OnceCell::new();
+
+/// Walks `content/blog` exactly once for every configured locale and hands
+/// back the sorted (most recent first) post list alongside a path-keyed
+/// lookup for the requested locale, so the index and post templates can
+/// share a single pass of file I/O and Markdown compilation per locale.
+pub fn get_all_posts(locale: &str) -> &'static (Vec<Post>, HashMap<String, Post>) {
+    let by_locale = ALL_POSTS.get_or_init(|| {
+        let dirs = get_blog_directories();
+        LOCALES
+            .iter()
+            .map(|&locale| {
+                let mut posts: Vec<Post> = dirs
+                    .iter()
+                    .map(|path| {
+                        get_post_for_path(path, locale).expect("failed to build known post")
+                    })
+                    .collect();
+                posts.sort_by_key(|post| post.date);
+                posts.reverse();
+
+                let by_path = posts
+                    .iter()
+                    .map(|post| (post.path.clone(), post.clone()))
+                    .collect();
+
+                (locale.to_string(), (posts, by_path))
+            })
+            .collect()
+    });
+    by_locale
+        .get(locale)
+        .unwrap_or_else(|| by_locale.get(DEFAULT_LOCALE).expect("default locale missing"))
+}
+
+/// Every distinct tag in use across all posts in `locale`, for enumerating
+/// `/tag/<tag>/` build paths.
+pub fn get_all_tags(locale: &str) -> Vec<String> {
+    let (posts, _) = get_all_posts(locale);
+    let mut tags: Vec<String> = posts
+        .iter()
+        .flat_map(|post| post.tags.iter().cloned())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Scans `content/blog` fresh (bypassing the one-shot `ALL_POSTS` snapshot)
+/// for posts tagged `tag`, most recent first. Used as the incremental
+/// fallback when a tag isn't in the cached snapshot yet, e.g. a post added
+/// or retagged after the process started serving.
+pub fn get_posts_for_tag_fresh(tag: &str, locale: &str) -> Vec<Post> {
+    let mut posts: Vec<Post> = get_blog_directories()
+        .iter()
+        .filter_map(|path| get_post_for_path(path, locale).ok())
+        .filter(|post| post.tags.iter().any(|t| t == tag))
+        .collect();
+    posts.sort_by_key(|post| post.date);
+    posts.reverse();
+    posts
 }
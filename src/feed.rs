@@ -0,0 +1,104 @@
+use crate::data::Post;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const SITE_URL: &str = "https://russellduhon.com";
+
+/// URL path prefix for a locale, matching Perseus' own routing: the default
+/// locale is unprefixed (`/post/<slug>/`), every other locale is prefixed
+/// (`/<locale>/post/<slug>/`).
+pub fn locale_prefix(locale: &str) -> String {
+    if locale == crate::data::DEFAULT_LOCALE {
+        String::new()
+    } else {
+        format!("/{locale}")
+    }
+}
+
+/// Builds one locale's Atom feed from that locale's sorted post list.
+pub fn build_feed(posts: &[Post], locale: &str) -> String {
+    let prefix = locale_prefix(locale);
+    let mut entries = String::new();
+    for post in posts {
+        let link = format!("{SITE_URL}{prefix}/post/{}/", post.path);
+        entries.push_str(&format!(
+            "  <entry>\n    \
+             <title>{title}</title>\n    \
+             <id>{link}</id>\n    \
+             <link href=\"{link}\"/>\n    \
+             <updated>{updated}</updated>\n    \
+             <published>{published}</published>\n    \
+             <summary>{summary}</summary>\n    \
+             <content type=\"html\">{content}</content>\n  \
+             </entry>\n",
+            title = escape(&post.title),
+            updated = post.date.to_rfc3339(),
+            published = post.date.to_rfc3339(),
+            summary = escape(post.description.as_deref().unwrap_or_default()),
+            content = escape(&post.html),
+        ));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <feed xmlns=\"http://www.w3.org/2005/Atom\">\n  \
+         <title>Russell Duhon's Blog</title>\n  \
+         <id>{SITE_URL}{prefix}/</id>\n  \
+         <link href=\"{SITE_URL}{prefix}/feed.xml\" rel=\"self\"/>\n  \
+         <link href=\"{SITE_URL}{prefix}/\"/>\n\
+         {entries}\
+         </feed>\n"
+    )
+}
+
+/// Builds `sitemap.xml` across every locale: one `<url>` per post per
+/// locale, plus each locale's index.
+pub fn build_sitemap_all_locales() -> String {
+    let mut urls = String::new();
+    for &locale in crate::data::LOCALES {
+        let prefix = locale_prefix(locale);
+        urls.push_str(&format!("  <url>\n    <loc>{SITE_URL}{prefix}/</loc>\n  </url>\n"));
+
+        let (posts, _) = crate::data::get_all_posts(locale);
+        for post in posts {
+            urls.push_str(&format!(
+                "  <url>\n    <loc>{SITE_URL}{prefix}/post/{path}/</loc>\n    <lastmod>{lastmod}</lastmod>\n  </url>\n",
+                path = post.path,
+                lastmod = post.date.format("%Y-%m-%d"),
+            ));
+        }
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n\
+         {urls}\
+         </urlset>\n"
+    )
+}
+
+fn escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Writes `feed.xml` (one per locale) and a combined `sitemap.xml` into the
+/// exported `dist` directory, alongside the HTML `perseus export` already
+/// wrote there.
+pub fn write_export_artifacts(dist_dir: &Path) -> io::Result<()> {
+    for &locale in crate::data::LOCALES {
+        let (posts, _) = crate::data::get_all_posts(locale);
+        let locale_dir = if locale == crate::data::DEFAULT_LOCALE {
+            dist_dir.to_path_buf()
+        } else {
+            let dir = dist_dir.join(locale);
+            fs::create_dir_all(&dir)?;
+            dir
+        };
+        fs::write(locale_dir.join("feed.xml"), build_feed(posts, locale))?;
+    }
+    fs::write(dist_dir.join("sitemap.xml"), build_sitemap_all_locales())?;
+    Ok(())
+}
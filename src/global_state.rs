@@ -0,0 +1,24 @@
+use crate::data::Post;
+use perseus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Site-wide state built once per run and shared by every template, so we
+/// don't re-read and re-render `content/blog` once per template that needs
+/// the post list.
+#[derive(Serialize, Deserialize, Clone, ReactiveState, PartialEq)]
+#[rx(alias = "GlobalStateRx")]
+pub struct GlobalState {
+    pub posts: Vec<Post>,
+}
+
+#[engine_only_fn]
+async fn get_build_state(info: StateGeneratorInfo<()>) -> GlobalState {
+    let (posts, _) = crate::data::get_all_posts(&info.locale);
+    GlobalState {
+        posts: posts.clone(),
+    }
+}
+
+pub fn get_global_state_creator() -> GlobalStateCreator {
+    GlobalStateCreator::new().build_state_fn(get_build_state)
+}
@@ -0,0 +1,34 @@
+pub mod data;
+pub mod feed;
+mod global_state;
+mod templates;
+
+use perseus::prelude::*;
+
+#[perseus::main(perseus_warp::dflt_server)]
+pub fn main<G: Html>() -> PerseusApp<G> {
+    PerseusApp::new()
+        .template(crate::templates::index::get_template)
+        .template(crate::templates::post::get_template)
+        .template(crate::templates::tag::get_template)
+        .global_state_creator(crate::global_state::get_global_state_creator())
+        .locales(
+            crate::data::DEFAULT_LOCALE,
+            &crate::data::LOCALES
+                .iter()
+                .filter(|&&locale| locale != crate::data::DEFAULT_LOCALE)
+                .copied()
+                .collect::<Vec<_>>(),
+        )
+        .index_view(|cx| {
+            view! { cx,
+                head {
+                    meta(charset="UTF-8")
+                    meta(name="viewport", content="width=device-width, initial-scale=1.0")
+                }
+                body {
+                    PerseusRoot()
+                }
+            }
+        })
+}
@@ -1,16 +1,66 @@
-use crate::data::Post;
+use crate::data::PostRx;
+use crate::global_state::GlobalStateRx;
 use perseus::prelude::*;
 use serde::{Deserialize, Serialize};
 use sycamore::prelude::*;
 
-#[derive(Serialize, Deserialize, Clone, ReactiveState)]
-#[rx(alias = "IndexRx")]
-struct Index {
-    posts: Vec<Post>,
+/// Just enough per-locale state to build this page's `hreflang` metadata;
+/// the post list itself comes from the shared global state. `<html lang>`
+/// is set by Perseus itself from `PerseusApp::locales`, not here.
+#[derive(Serialize, Deserialize, Clone, ReactiveState, PartialEq)]
+#[rx(alias = "IndexMetaRx")]
+struct IndexMeta {
+    locale: String,
+}
+
+/// Renders a reverse-chronological list of posts. Shared by the index page
+/// and the per-tag listing pages.
+pub fn post_list<G: Html>(cx: Scope, posts: &RcSignal<Vec<PostRx>>) -> View<G> {
+    view! { cx,
+        Keyed(
+            iterable=posts,
+            view=|cx, post| view! { cx,
+                div(style="margin-bottom: 3em;") {
+                    h4 {
+                        a(href=format!("post/{}/", post.path)) { (post.title.clone()) }
+                    }
+                    h6(style="display: inline") {
+                        span { (post.date.date_naive().format("%-d %B %C%y")) }
+                    }
+                    span {
+                        (View::new_fragment(
+                            post.tags
+                                .iter()
+                                .map(|tag| {
+                                    let tag = tag.clone();
+                                    let prefix = crate::feed::locale_prefix(&post.locale);
+                                    view! { cx,
+                                        " "
+                                        a(href=format!("{prefix}/tag/{tag}/")) { "#" (tag) }
+                                    }
+                                })
+                                .collect()
+                        ))
+                    }
+                    (if let Some(description) = post.description.clone() {
+                        view! { cx,
+                            blockquote {
+                                (description)
+                            }
+                        }
+                    } else {
+                        view! { cx, }
+                    })
+                }
+            },
+            key=|post| post.path.clone()
+        )
+    }
 }
 
 #[auto_scope]
-fn index_page<G: Html>(cx: Scope, state: &IndexRx) -> View<G> {
+fn index_page<G: Html>(cx: Scope) -> View<G> {
+    let state = RenderCtx::from_ctx(cx).get_global_state::<GlobalStateRx>(cx);
     view! { cx,
         // Don't worry, there are much better ways of styling in Perseus!
         div {
@@ -26,29 +76,7 @@ fn index_page<G: Html>(cx: Scope, state: &IndexRx) -> View<G> {
                 h3 { "Blog"}
 
                 div {
-                    Keyed(
-                        iterable=&state.posts,
-                        view=|cx, post| view! { cx,
-                            div(style="margin-bottom: 3em;") {
-                                h4 {
-                                    a(href=format!("post/{}/", post.path)) { (post.title.clone()) }
-                                }
-                                h6(style="display: inline") {
-                                    span { (post.date.date_naive().format("%-d %B %C%y")) }
-                                }
-                                (if let Some(description) = post.description.clone() {
-                                    view! { cx,
-                                        blockquote {
-                                            (description)
-                                        }
-                                    }
-                                } else {
-                                    view! { cx, }
-                                })
-                            }
-                        },
-                        key=|post| post.path.clone()
-                    )
+                    (post_list(cx, &state.posts))
                 }
             }
         }
@@ -56,30 +84,38 @@ fn index_page<G: Html>(cx: Scope, state: &IndexRx) -> View<G> {
 }
 
 #[engine_only_fn]
-fn head(cx: Scope) -> View<SsrNode> {
-    view! { cx,
-        title { "Russell Duhon" }
-        link(rel="stylesheet", href="https://unpkg.com/sakura.css/css/sakura.css", media="screen")
-        link(rel="stylesheet", href=".perseus/static/extra.css")
+async fn get_build_state(info: StateGeneratorInfo<()>) -> IndexMeta {
+    IndexMeta {
+        locale: info.locale,
     }
 }
 
 #[engine_only_fn]
-async fn get_build_state(_generator: StateGeneratorInfo<()>) -> Index {
-    let mut posts: Vec<Post> = crate::data::get_blog_directories()
-        .iter()
-        .map(crate::data::get_post_for_path)
-        .collect();
-    posts.sort_by_key(|post| post.date.clone());
-    Index {
-        posts: posts.into_iter().rev().collect(),
+fn head(cx: Scope, state: IndexMeta) -> View<SsrNode> {
+    let feed_href = format!("{}/feed.xml", crate::feed::locale_prefix(&state.locale));
+    view! { cx,
+        title { "Russell Duhon" }
+        link(rel="stylesheet", href="https://unpkg.com/sakura.css/css/sakura.css", media="screen")
+        link(rel="stylesheet", href=".perseus/static/extra.css")
+        link(rel="alternate", type="application/atom+xml", href=feed_href, title="Russell Duhon's Blog")
+        // `<html lang>` itself comes from Perseus' own locale-aware shell, not this head.
+        meta(http-equiv="content-language", content=state.locale.clone())
+        (View::new_fragment(
+            crate::data::LOCALES
+                .iter()
+                .filter(|locale| **locale != state.locale)
+                .map(|locale| view! { cx,
+                    link(rel="alternate", hreflang=locale.to_string(), href=format!("{}/", crate::feed::locale_prefix(locale)))
+                })
+                .collect()
+        ))
     }
 }
 
 pub fn get_template<G: Html>() -> Template<G> {
     Template::build("index")
         .build_state_fn(get_build_state)
-        .view_with_state(index_page)
-        .head(head)
+        .view(index_page)
+        .head_with_state(head)
         .build()
 }
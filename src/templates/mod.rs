@@ -0,0 +1,3 @@
+pub mod index;
+pub mod post;
+pub mod tag;
@@ -1,4 +1,4 @@
-use crate::data::Post;
+use crate::data::{Post, PostError};
 use perseus::prelude::*;
 #[cfg(engine)]
 use perseus::utils::get_path_prefix_server;
@@ -6,19 +6,30 @@ use sycamore::prelude::*;
 
 #[auto_scope]
 fn post_page<G: Html>(cx: Scope, state: &crate::data::PostRx) -> View<G> {
+    let home_href = format!("{}/", crate::feed::locale_prefix(&state.locale.get()));
     view! { cx,
         div {
-            h6 { a(href="/") { "home" }}
+            h6 { a(href=home_href.clone()) { "home" }}
             h1 { (state.title.get()) }
+            h6 {
+                (View::new_fragment(
+                    state.tags.get().iter().map(|tag| {
+                        let tag = tag.clone();
+                        let prefix = crate::feed::locale_prefix(&state.locale.get());
+                        view! { cx,
+                            " "
+                            a(href=format!("{prefix}/tag/{tag}/")) { "#" (tag) }
+                        }
+                    }).collect()
+                ))
+            }
             div(
                 dangerously_set_inner_html = &state.html.get()
             )
-            h6 { a(href="/") { "home" }}
+            h6 { a(href=home_href.clone()) { "home" }}
             script(defer=true, src="https://app.tinyanalytics.io/pixel/MB6jAtnTO5M0SZ9n")
             script(defer=true, async=true, src="https://scripts.simpleanalyticscdn.com/latest.js")
             script(defer=true, src="https://plausible.io/js/script.js", data-domain="russellduhon.com" )
-            script(src="https://cdn.jsdelivr.net/npm/prismjs@1.29.0/components/prism-core.min.js")
-            script(src="https://cdn.jsdelivr.net/npm/prismjs@1.29.0/plugins/autoloader/prism-autoloader.min.js")
         }
     }
 }
@@ -29,11 +40,14 @@ fn head(cx: Scope, post: Post) -> View<SsrNode> {
     let full_image_url = post
         .image
         .map(|url| format!("{}/{}", get_path_prefix_server(), url));
+    let alternates = crate::data::get_available_locales(&post.path);
     view! { cx,
         title { (post.title) }
         meta(property="og:title", content=full_title)
         meta(property="og:type", content="article")
         meta(property="article:published_time", content=post.date.to_rfc3339())
+        // `<html lang>` itself comes from Perseus' own locale-aware shell, not this head.
+        meta(http-equiv="content-language", content=post.locale.clone())
         (if let Some(url) = full_image_url.clone() {
             view! { cx,
                 meta(property="og:image", content=url)
@@ -51,7 +65,19 @@ fn head(cx: Scope, post: Post) -> View<SsrNode> {
         meta(property="og:site_name", content="Russell Duhon's Blog")
         link(rel="stylesheet", href="https://unpkg.com/sakura.css/css/sakura.css", media="screen")
         link(rel="stylesheet", href=".perseus/static/extra.css")
-        link(rel="stylesheet", href="https://cdn.jsdelivr.net/npm/prismjs@1.29.0/themes/prism.min.css")
+        (View::new_fragment(
+            alternates
+                .into_iter()
+                .filter(|locale| *locale != post.locale)
+                .map(|locale| {
+                    let path = post.path.clone();
+                    let prefix = crate::feed::locale_prefix(&locale);
+                    view! { cx,
+                        link(rel="alternate", hreflang=locale.clone(), href=format!("{prefix}/post/{path}/"))
+                    }
+                })
+                .collect()
+        ))
     }
 }
 
@@ -66,15 +92,34 @@ async fn get_build_paths() -> BuildPaths {
 }
 
 #[engine_only_fn]
-async fn get_build_state(StateGeneratorInfo { path, .. }: StateGeneratorInfo<()>) -> Post {
-    // TODO do I need a way to 404 here? What happens if we request something missing?
-    crate::data::get_post_for_path(&path)
+async fn get_build_state(
+    StateGeneratorInfo { path, locale, .. }: StateGeneratorInfo<()>,
+) -> RenderFnResultWithCause<Post> {
+    // Known posts come out of the shared global-state pass; anything else is
+    // an incremental match, so fall back to reading it fresh off disk and let
+    // a genuinely missing file turn into a 404 instead of a panic.
+    let (_, by_path) = crate::data::get_all_posts(&locale);
+    if let Some(post) = by_path.get(&path) {
+        return Ok(post.clone());
+    }
+
+    crate::data::get_post_for_path(&path, &locale).map_err(|err| match err {
+        PostError::NotFound(_) => GenericErrorWithCause {
+            error: err.into(),
+            cause: ErrorCause::Client(Some(404)),
+        },
+        _ => GenericErrorWithCause {
+            error: err.into(),
+            cause: ErrorCause::Server(None),
+        },
+    })
 }
 
 pub fn get_template<G: Html>() -> Template<G> {
     Template::build("post")
         .build_paths_fn(get_build_paths)
         .build_state_fn(get_build_state)
+        .incremental_generation()
         .view_with_state(post_page)
         .head_with_state(head)
         .build()
@@ -0,0 +1,87 @@
+use crate::data::Post;
+use perseus::prelude::*;
+use serde::{Deserialize, Serialize};
+use sycamore::prelude::*;
+
+#[derive(Serialize, Deserialize, Clone, ReactiveState, PartialEq)]
+#[rx(alias = "TagPageRx")]
+struct TagPage {
+    tag: String,
+    posts: Vec<Post>,
+    locale: String,
+}
+
+#[auto_scope]
+fn tag_page<G: Html>(cx: Scope, state: &TagPageRx) -> View<G> {
+    let home_href = format!("{}/", crate::feed::locale_prefix(&state.locale.get()));
+    view! { cx,
+        div {
+            h6 { a(href=home_href) { "home" } }
+            h1 { "Posts tagged #" (state.tag.get()) }
+            div {
+                (crate::templates::index::post_list(cx, &state.posts))
+            }
+        }
+    }
+}
+
+#[engine_only_fn]
+fn head(cx: Scope, state: TagPage) -> View<SsrNode> {
+    view! { cx,
+        title { (format!("Posts tagged #{} - Russell Duhon", state.tag)) }
+        link(rel="stylesheet", href="https://unpkg.com/sakura.css/css/sakura.css", media="screen")
+        link(rel="stylesheet", href=".perseus/static/extra.css")
+    }
+}
+
+#[engine_only_fn]
+async fn get_build_paths() -> BuildPaths {
+    BuildPaths {
+        // These will all become URLs at `/tag/<name>`
+        paths: crate::data::get_all_tags(crate::data::DEFAULT_LOCALE),
+        extra: ().into(),
+    }
+}
+
+#[engine_only_fn]
+async fn get_build_state(
+    StateGeneratorInfo { path, locale, .. }: StateGeneratorInfo<()>,
+) -> RenderFnResultWithCause<TagPage> {
+    let (posts, _) = crate::data::get_all_posts(&locale);
+    let mut matching: Vec<Post> = posts
+        .iter()
+        .filter(|post| post.tags.contains(&path))
+        .cloned()
+        .collect();
+
+    // The cached snapshot is built once and never refreshed, so a tag that
+    // didn't exist yet when it was built (new post, or a tag added to an
+    // existing one) isn't in it. Re-scan disk directly before giving up, the
+    // same way `post::get_build_state` falls back to a live file read.
+    if matching.is_empty() {
+        matching = crate::data::get_posts_for_tag_fresh(&path, &locale);
+    }
+
+    if matching.is_empty() {
+        return Err(GenericErrorWithCause {
+            error: format!("no posts tagged {path}").into(),
+            cause: ErrorCause::Client(Some(404)),
+        });
+    }
+
+    Ok(TagPage {
+        tag: path,
+        posts: matching,
+        locale,
+    })
+}
+
+pub fn get_template<G: Html>() -> Template<G> {
+    Template::build("tag")
+        .build_paths_fn(get_build_paths)
+        .build_state_fn(get_build_state)
+        .incremental_generation()
+        .view_with_state(tag_page)
+        .head_with_state(head)
+        .build()
+}